@@ -0,0 +1,117 @@
+//! Artifacts a tool run produced as a side effect (e.g. an index file a
+//! codexify run wrote to disk), captured alongside its JSON response instead
+//! of requiring the tool to inline everything into stdout.
+//!
+//! Opt-in via `SubprocessConfig::artifact_dir`: when set, the bridge creates
+//! that directory before spawning, exports it to the child as
+//! [`ARTIFACT_DIR_ENV`], and after a successful run scans it for files to
+//! attach to the `CapabilityResult`.
+
+use crate::{BridgeError, BridgeResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+/// Env var a configured `artifact_dir` is exported under, so a tool knows
+/// where to write generated files.
+pub const ARTIFACT_DIR_ENV: &str = "ARTIFACT_DIR";
+
+/// A file a tool run produced, discovered by scanning its `artifact_dir`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Artifact {
+    /// Path relative to the run's `artifact_dir`.
+    pub relative_path: String,
+    pub size_bytes: u64,
+    pub content_hash: String,
+}
+
+/// Create `artifact_dir` (and its parents) if it doesn't already exist.
+pub fn prepare_artifact_dir(artifact_dir: &str) -> BridgeResult<()> {
+    fs::create_dir_all(artifact_dir)?;
+    Ok(())
+}
+
+/// Recursively scan `artifact_dir`, recording each file's relative path,
+/// size, and SHA-256 content hash.
+pub fn collect_artifacts(artifact_dir: &str) -> BridgeResult<Vec<Artifact>> {
+    let root = Path::new(artifact_dir);
+    let mut artifacts = Vec::new();
+    collect_into(root, root, &mut artifacts)?;
+    Ok(artifacts)
+}
+
+fn collect_into(root: &Path, dir: &Path, artifacts: &mut Vec<Artifact>) -> BridgeResult<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_into(root, &path, artifacts)?;
+            continue;
+        }
+
+        let bytes = fs::read(&path)?;
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes);
+        let content_hash = format!("{:x}", hasher.finalize());
+
+        let relative_path = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        artifacts.push(Artifact {
+            relative_path,
+            size_bytes: bytes.len() as u64,
+            content_hash,
+        });
+    }
+
+    Ok(())
+}
+
+/// Read back the bytes of `artifact`, resolved against the `artifact_dir`
+/// it was collected from.
+pub fn read_artifact(artifact_dir: &str, artifact: &Artifact) -> BridgeResult<Vec<u8>> {
+    let path = Path::new(artifact_dir).join(&artifact.relative_path);
+    fs::read(path).map_err(BridgeError::Io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_collect_artifacts_finds_nested_files() {
+        let dir = tempdir().unwrap();
+        let artifact_dir = dir.path().to_string_lossy().to_string();
+
+        fs::write(dir.path().join("index.json"), b"{}").unwrap();
+        fs::create_dir(dir.path().join("nested")).unwrap();
+        fs::write(dir.path().join("nested/graph.json"), b"[]").unwrap();
+
+        let mut artifacts = collect_artifacts(&artifact_dir).unwrap();
+        artifacts.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+        assert_eq!(artifacts.len(), 2);
+        assert_eq!(artifacts[0].relative_path, "index.json");
+        assert_eq!(artifacts[0].size_bytes, 2);
+        assert_eq!(artifacts[1].relative_path, "nested/graph.json");
+    }
+
+    #[test]
+    fn test_read_artifact_round_trips_contents() {
+        let dir = tempdir().unwrap();
+        let artifact_dir = dir.path().to_string_lossy().to_string();
+        fs::write(dir.path().join("report.txt"), b"hello").unwrap();
+
+        let artifacts = collect_artifacts(&artifact_dir).unwrap();
+        let bytes = read_artifact(&artifact_dir, &artifacts[0]).unwrap();
+        assert_eq!(bytes, b"hello");
+    }
+}
@@ -0,0 +1,333 @@
+//! Benchmark harness that replays bridge calls against a named workload file
+//! and reports latency/throughput, so regressions in the subprocess layer
+//! (e.g. after a worker-pool change) can be caught across checked-in
+//! workload files.
+//!
+//! A workload file looks like:
+//!
+//! ```json
+//! {
+//!   "name": "codexify-small-files",
+//!   "tool": "codexify",
+//!   "warmup": 2,
+//!   "runs": 10,
+//!   "requests": [ { "file_path": "fixtures/a.txt" } ]
+//! }
+//! ```
+//!
+//! `warmup` iterations are discarded before timing begins; `runs` iterations
+//! are timed per request. See [`run_workload`] for the `bin/bench` entry
+//! point that drives this.
+
+use crate::codexify::{run_codexify, CodexifyRequest};
+use crate::ritual_engine::{run_ritual_engine, RitualEngineRequest};
+use crate::{BridgeError, BridgeResult, CapabilityResult, SubprocessConfig};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// A workload file describing what to replay and how many times.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workload {
+    pub name: String,
+    pub tool: String,
+    pub warmup: usize,
+    pub runs: usize,
+    pub requests: Vec<serde_json::Value>,
+    /// Path to the tool's manifest JSON. Only consulted for tools other
+    /// than the `codexify`/`ritual_engine` built-ins, which are dispatched
+    /// directly; defaults to `manifests/<tool>.json` if unset.
+    #[serde(default)]
+    pub manifest_path: Option<String>,
+}
+
+/// Load a workload file from disk.
+pub fn load_workload<P: AsRef<Path>>(path: P) -> BridgeResult<Workload> {
+    let content = fs::read_to_string(path)?;
+    let workload: Workload = serde_json::from_str(&content)?;
+    Ok(workload)
+}
+
+/// Latency distribution across all timed calls in a workload run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub min_ms: u64,
+    pub median_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+impl LatencyStats {
+    fn from_durations_ms(mut durations_ms: Vec<u64>) -> Self {
+        durations_ms.sort_unstable();
+        let percentile = |p: f64| -> u64 {
+            if durations_ms.is_empty() {
+                return 0;
+            }
+            let idx = ((durations_ms.len() as f64 - 1.0) * p).round() as usize;
+            durations_ms[idx.min(durations_ms.len() - 1)]
+        };
+
+        Self {
+            min_ms: *durations_ms.first().unwrap_or(&0),
+            median_ms: percentile(0.5),
+            p95_ms: percentile(0.95),
+            max_ms: *durations_ms.last().unwrap_or(&0),
+        }
+    }
+}
+
+/// Environment captured alongside a benchmark result for regression tracking.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentInfo {
+    pub git_commit: Option<String>,
+    pub host: String,
+    pub python_version: Option<String>,
+}
+
+impl EnvironmentInfo {
+    fn capture() -> Self {
+        Self {
+            git_commit: run_and_capture_stdout("git", &["rev-parse", "HEAD"]),
+            host: hostname(),
+            python_version: run_and_capture_stdout("python3", &["--version"]),
+        }
+    }
+}
+
+fn run_and_capture_stdout(command: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(command).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let combined = if output.stdout.is_empty() {
+        output.stderr
+    } else {
+        output.stdout
+    };
+    Some(String::from_utf8_lossy(&combined).trim().to_string())
+}
+
+fn hostname() -> String {
+    run_and_capture_stdout("hostname", &[]).unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Result of replaying a single workload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub workload: String,
+    pub tool: String,
+    pub total_calls: usize,
+    pub latency: LatencyStats,
+    pub throughput_per_sec: f64,
+    pub environment: EnvironmentInfo,
+}
+
+/// Replay `workload` through the bridge, discarding `warmup` iterations and
+/// timing `runs` iterations per request.
+pub async fn run_workload(workload: &Workload) -> BridgeResult<BenchResult> {
+    let config = SubprocessConfig::default();
+    let total_iterations = workload.warmup + workload.runs;
+    let mut durations_ms = Vec::with_capacity(workload.requests.len() * workload.runs);
+
+    for request in &workload.requests {
+        for iteration in 0..total_iterations {
+            let duration_ms = dispatch_once(workload, request, &config).await?;
+            if iteration >= workload.warmup {
+                durations_ms.push(duration_ms);
+            }
+        }
+    }
+
+    let total_calls = durations_ms.len();
+    let total_ms: u64 = durations_ms.iter().sum();
+    let throughput_per_sec = if total_ms > 0 {
+        total_calls as f64 / (total_ms as f64 / 1000.0)
+    } else {
+        0.0
+    };
+
+    Ok(BenchResult {
+        workload: workload.name.clone(),
+        tool: workload.tool.clone(),
+        total_calls,
+        latency: LatencyStats::from_durations_ms(durations_ms),
+        throughput_per_sec,
+        environment: EnvironmentInfo::capture(),
+    })
+}
+
+async fn dispatch_once(
+    workload: &Workload,
+    request: &serde_json::Value,
+    config: &SubprocessConfig,
+) -> BridgeResult<u64> {
+    let tool = workload.tool.as_str();
+    let duration_ms = match tool {
+        "codexify" => {
+            let request: CodexifyRequest = serde_json::from_value(request.clone())?;
+            latency_sample(run_codexify(request, Some(config.clone())).await?, tool)?
+        }
+        "ritual_engine" => {
+            let request: RitualEngineRequest = serde_json::from_value(request.clone())?;
+            latency_sample(
+                run_ritual_engine(request, Some(config.clone())).await?,
+                tool,
+            )?
+        }
+        // Any other tool is assumed manifest-driven, matching how `run_tool`
+        // makes a new Python tool pluggable with zero new Rust code — a
+        // hardcoded match arm per tool here would defeat that.
+        other => {
+            let manifest_path = workload
+                .manifest_path
+                .clone()
+                .unwrap_or_else(|| format!("manifests/{}.json", other));
+            let manifest = crate::manifest::load_manifest(&manifest_path)?;
+            latency_sample(
+                crate::runner::run_tool(&manifest, request.clone(), Some(config.clone())).await?,
+                other,
+            )?
+        }
+    };
+
+    Ok(duration_ms)
+}
+
+/// Pull the latency sample out of a tool call's result, failing the bench
+/// run instead of folding a failed call (`run_codexify`/`run_ritual_engine`
+/// turn subprocess errors into `CapabilityResult::error` rather than
+/// propagating `Err`) into the stats as a normal — often suspiciously fast
+/// — latency sample.
+fn latency_sample<T>(result: CapabilityResult<T>, tool: &str) -> BridgeResult<u64> {
+    if !result.success {
+        return Err(BridgeError::InvalidOutput(format!(
+            "{} call failed during bench run: {}",
+            tool,
+            result.error.unwrap_or_else(|| "unknown error".to_string())
+        )));
+    }
+    Ok(result.metadata.duration_ms)
+}
+
+/// POST a [`BenchResult`] to an HTTP endpoint for regression tracking.
+pub async fn report_result(result: &BenchResult, report_url: &str) -> BridgeResult<()> {
+    let client = reqwest::Client::new();
+    client
+        .post(report_url)
+        .json(result)
+        .send()
+        .await
+        .map_err(|e| BridgeError::InvalidOutput(format!("failed to POST bench report: {}", e)))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_stats_from_durations() {
+        let stats = LatencyStats::from_durations_ms(vec![10, 20, 30, 40, 50]);
+        assert_eq!(stats.min_ms, 10);
+        assert_eq!(stats.median_ms, 30);
+        assert_eq!(stats.max_ms, 50);
+    }
+
+    #[test]
+    fn test_latency_sample_rejects_failed_call() {
+        let failed: CapabilityResult<()> = CapabilityResult::error(
+            "process exited with non-zero status: 1".to_string(),
+            "codexify",
+            std::time::Duration::from_millis(5),
+            "1.0.0",
+        );
+
+        let err = latency_sample(failed, "codexify").unwrap_err();
+        assert!(err.to_string().contains("codexify call failed"));
+    }
+
+    #[test]
+    fn test_latency_sample_accepts_successful_call() {
+        let ok = CapabilityResult::success((), "codexify", std::time::Duration::from_millis(42), "1.0.0");
+        assert_eq!(latency_sample(ok, "codexify").unwrap(), 42);
+    }
+
+    #[test]
+    fn test_workload_round_trips_through_json() {
+        let workload = Workload {
+            name: "example".to_string(),
+            tool: "codexify".to_string(),
+            warmup: 1,
+            runs: 3,
+            requests: vec![serde_json::json!({ "file_path": "a.txt" })],
+            manifest_path: None,
+        };
+
+        let json = serde_json::to_string(&workload).unwrap();
+        let parsed: Workload = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.name, "example");
+        assert_eq!(parsed.runs, 3);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_once_routes_unknown_tool_through_manifest_runner() {
+        use crate::manifest::{InputSchema, Manifest, OutputSchema};
+        use std::collections::HashMap;
+        use std::io::Write;
+        use tempfile::NamedTempFile;
+
+        let mut script_file = NamedTempFile::new().unwrap();
+        write!(
+            script_file,
+            "import json, sys\ndata = json.load(sys.stdin)\nprint(json.dumps({{'greeting': 'hi ' + data['name']}}))\n"
+        )
+        .unwrap();
+
+        let manifest = Manifest {
+            name: "greeter".to_string(),
+            version: "1.0.0".to_string(),
+            description: "echoes a greeting".to_string(),
+            language: "python".to_string(),
+            entry_point: script_file.path().to_string_lossy().to_string(),
+            capabilities: vec!["greet".to_string()],
+            schema: None,
+            timeout_sec: 5,
+            requirements: HashMap::new(),
+            inputs: HashMap::from([(
+                "name".to_string(),
+                InputSchema {
+                    r#type: "string".to_string(),
+                    description: "who to greet".to_string(),
+                    required: true,
+                    default: None,
+                },
+            )]),
+            outputs: HashMap::from([(
+                "greeting".to_string(),
+                OutputSchema {
+                    r#type: "string".to_string(),
+                    description: "the greeting".to_string(),
+                },
+            )]),
+        };
+        let manifest_file = NamedTempFile::new().unwrap();
+        crate::manifest::save_manifest(&manifest, manifest_file.path()).unwrap();
+
+        let workload = Workload {
+            name: "greeter-bench".to_string(),
+            tool: "greeter".to_string(),
+            warmup: 0,
+            runs: 1,
+            requests: vec![serde_json::json!({ "name": "fixture" })],
+            manifest_path: Some(manifest_file.path().to_string_lossy().to_string()),
+        };
+        let config = SubprocessConfig::default();
+
+        let duration_ms = dispatch_once(&workload, &workload.requests[0], &config)
+            .await
+            .unwrap();
+        assert!(duration_ms < 5_000);
+    }
+}
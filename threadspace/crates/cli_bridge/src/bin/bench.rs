@@ -0,0 +1,54 @@
+//! `cargo run --bin bench -- <workload.json> [--report-url <url>]`
+//!
+//! Replays a checked-in workload file through the bridge and prints the
+//! resulting latency/throughput report as JSON. Pass `--report-url` to also
+//! POST the report to an HTTP endpoint for regression tracking.
+
+use cli_bridge::bench::{load_workload, report_result, run_workload};
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let mut workload_path = None;
+    let mut report_url = None;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--report-url" => {
+                report_url = iter.next().cloned();
+            }
+            path => workload_path = Some(path.to_string()),
+        }
+    }
+
+    let Some(workload_path) = workload_path else {
+        eprintln!("usage: bench <workload.json> [--report-url <url>]");
+        std::process::exit(2);
+    };
+
+    let workload = match load_workload(&workload_path) {
+        Ok(workload) => workload,
+        Err(e) => {
+            eprintln!("failed to load workload '{}': {}", workload_path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let result = match run_workload(&workload).await {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("benchmark run failed: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    println!("{}", serde_json::to_string_pretty(&result).unwrap());
+
+    if let Some(url) = report_url {
+        if let Err(e) = report_result(&result, &url).await {
+            eprintln!("failed to report results to {}: {}", url, e);
+            std::process::exit(1);
+        }
+    }
+}
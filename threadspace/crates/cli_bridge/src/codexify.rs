@@ -45,12 +45,24 @@ pub async fn run_codexify(
     let duration = start_time.elapsed();
     
     match result {
-        Ok(response) => Ok(CapabilityResult::success(
-            response,
-            "codexify",
-            duration,
-            "1.0.0",
-        )),
+        Ok(response) => {
+            let mut capability_result =
+                CapabilityResult::success(response, "codexify", duration, "1.0.0");
+            if let Some(artifact_dir) = &config.artifact_dir {
+                match crate::artifact::collect_artifacts(artifact_dir) {
+                    Ok(artifacts) => capability_result = capability_result.with_artifacts(artifacts),
+                    Err(e) => {
+                        return Ok(CapabilityResult::error(
+                            e.to_string(),
+                            "codexify",
+                            duration,
+                            "1.0.0",
+                        ))
+                    }
+                }
+            }
+            Ok(capability_result)
+        }
         Err(e) => Ok(CapabilityResult::error(
             e.to_string(),
             "codexify",
@@ -133,6 +145,26 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_codexify_success_fixture_matches_checked_in_spec() {
+        let fixture = crate::fixtures::load_fixture_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/codexify_success.json"
+        ))
+        .unwrap();
+        assert!(crate::fixtures::run_fixture(&fixture).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_codexify_failure_fixture_matches_checked_in_spec() {
+        let fixture = crate::fixtures::load_fixture_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/codexify_failure.json"
+        ))
+        .unwrap();
+        assert!(crate::fixtures::run_fixture(&fixture).await.is_ok());
+    }
+
     #[test]
     fn test_codexify_request_serialization() {
         let request = CodexifyRequest {
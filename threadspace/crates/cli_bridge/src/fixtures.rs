@@ -0,0 +1,328 @@
+//! Declarative subprocess test fixtures with regex output assertions.
+//!
+//! The existing tests shell out to real `python3` and assert on a single
+//! parsed JSON value, which gets unwieldy once a test wants to check log
+//! lines, stderr output, or exit codes too. A [`Fixture`] declares all of
+//! that up front — input payload, command, and a regex per stream — and
+//! [`run_fixture`] runs it through the same [`LocalTransport`] the rest of
+//! the bridge uses, producing a clear [`FixtureFailure`] on mismatch instead
+//! of a bare assertion panic.
+//!
+//! Fixtures are declarative: [`load_fixture`]/[`load_fixtures`] parse them
+//! straight from a JSON spec, so a new fixture is a JSON object, not a Rust
+//! struct literal. `expected_output` in that spec is a map of stream name
+//! (`"stdout"`/`"stderr"`) to one regex or a list of them.
+//!
+//! A few fixtures standing in for `codexify`/`ritual_engine` are checked
+//! into `fixtures/*.json` and loaded with [`load_fixture_file`]; the
+//! `codexify`/`ritual_engine` test modules run them through [`run_fixture`]
+//! so those integration tests assert on emitted log lines and error formats
+//! without needing a full Python backend.
+
+use crate::transport::{LocalTransport, Transport};
+use crate::SubprocessConfig;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Which output stream an expectation applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// A declarative expectation for one subprocess run.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "FixtureSpec")]
+pub struct Fixture {
+    pub name: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub input: Value,
+    pub expected_exit_code: i32,
+    /// Regex patterns that must each match somewhere in the named stream.
+    pub expected_output: Vec<(Stream, String)>,
+    pub max_duration: Option<Duration>,
+}
+
+/// One or several regexes for a single stream in a [`FixtureSpec`].
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PatternSpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl PatternSpec {
+    fn into_patterns(self) -> Vec<String> {
+        match self {
+            PatternSpec::One(pattern) => vec![pattern],
+            PatternSpec::Many(patterns) => patterns,
+        }
+    }
+}
+
+/// The JSON shape a [`Fixture`] is declared in: a map of file-descriptor
+/// name to expected-output-regex (or regexes) instead of Rust tuples.
+#[derive(Debug, Deserialize)]
+struct FixtureSpec {
+    name: String,
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    input: Value,
+    #[serde(default)]
+    expected_exit_code: i32,
+    #[serde(default)]
+    expected_output: HashMap<Stream, PatternSpec>,
+    max_duration_secs: Option<u64>,
+}
+
+impl From<FixtureSpec> for Fixture {
+    fn from(spec: FixtureSpec) -> Self {
+        let mut expected_output = Vec::new();
+        for (stream, patterns) in spec.expected_output {
+            for pattern in patterns.into_patterns() {
+                expected_output.push((stream, pattern));
+            }
+        }
+        Fixture {
+            name: spec.name,
+            command: spec.command,
+            args: spec.args,
+            input: spec.input,
+            expected_exit_code: spec.expected_exit_code,
+            expected_output,
+            max_duration: spec.max_duration_secs.map(Duration::from_secs),
+        }
+    }
+}
+
+/// Parse a single fixture from its JSON spec.
+pub fn load_fixture(json: &str) -> Result<Fixture, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+/// Parse a JSON array of fixture specs.
+pub fn load_fixtures(json: &str) -> Result<Vec<Fixture>, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+/// Read and parse a single fixture from a checked-in JSON spec file, e.g.
+/// one of the specs under `fixtures/`.
+pub fn load_fixture_file<P: AsRef<std::path::Path>>(path: P) -> crate::BridgeResult<Fixture> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(load_fixture(&content)?)
+}
+
+/// Read and parse a JSON array of fixture specs from a checked-in file.
+pub fn load_fixtures_file<P: AsRef<std::path::Path>>(
+    path: P,
+) -> crate::BridgeResult<Vec<Fixture>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(load_fixtures(&content)?)
+}
+
+/// Why a fixture failed, with enough detail to diagnose without rerunning it.
+#[derive(Debug)]
+pub struct FixtureFailure {
+    pub fixture: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for FixtureFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fixture '{}' failed: {}", self.fixture, self.reason)
+    }
+}
+
+impl std::error::Error for FixtureFailure {}
+
+/// Run `fixture` and assert its exit code, duration, and stream contents.
+pub async fn run_fixture(fixture: &Fixture) -> Result<(), FixtureFailure> {
+    let fail = |reason: String| FixtureFailure {
+        fixture: fixture.name.clone(),
+        reason,
+    };
+
+    let input_json = serde_json::to_string(&fixture.input)
+        .map_err(|e| fail(format!("could not serialize input: {}", e)))?;
+    let args: Vec<&str> = fixture.args.iter().map(String::as_str).collect();
+    let config = SubprocessConfig {
+        timeout: fixture.max_duration.unwrap_or(Duration::from_secs(30)),
+        ..Default::default()
+    };
+
+    let started = std::time::Instant::now();
+    let transport = LocalTransport;
+    let output = transport
+        .execute(&fixture.command, &args, &input_json, &config)
+        .await
+        .map_err(|e| fail(format!("process did not run to completion: {}", e)))?;
+    let elapsed = started.elapsed();
+
+    if let Some(max_duration) = fixture.max_duration {
+        if elapsed > max_duration {
+            return Err(fail(format!(
+                "took {:?}, exceeding max_duration {:?}",
+                elapsed, max_duration
+            )));
+        }
+    }
+
+    if output.exit_code != Some(fixture.expected_exit_code) {
+        return Err(fail(format!(
+            "expected exit code {}, got {:?}",
+            fixture.expected_exit_code, output.exit_code
+        )));
+    }
+
+    for (stream, pattern) in &fixture.expected_output {
+        let actual = match stream {
+            Stream::Stdout => String::from_utf8_lossy(&output.stdout),
+            Stream::Stderr => String::from_utf8_lossy(&output.stderr),
+        };
+        let regex =
+            Regex::new(pattern).map_err(|e| fail(format!("invalid regex '{}': {}", pattern, e)))?;
+        // `^`/`$` anchor the whole haystack (no multiline flag), so a
+        // trailing newline from e.g. `print()` would make an otherwise
+        // exact match fail. Trim it before matching.
+        if !regex.is_match(actual.trim_end_matches('\n')) {
+            return Err(fail(format!(
+                "{:?} did not match /{}/, got: {:?}",
+                stream, pattern, actual
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_fixture_passes_on_matching_echo() {
+        let fixture = Fixture {
+            name: "echo_stdout".to_string(),
+            command: "python3".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "import json, sys; data=json.load(sys.stdin); print(data['message'])".to_string(),
+            ],
+            input: json!({ "message": "hello fixture" }),
+            expected_exit_code: 0,
+            expected_output: vec![(Stream::Stdout, r"^hello fixture$".to_string())],
+            max_duration: Some(Duration::from_secs(5)),
+        };
+
+        assert!(run_fixture(&fixture).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fixture_reports_exit_code_mismatch() {
+        let fixture = Fixture {
+            name: "nonzero_exit".to_string(),
+            command: "python3".to_string(),
+            args: vec!["-c".to_string(), "import sys; sys.exit(1)".to_string()],
+            input: json!({}),
+            expected_exit_code: 0,
+            expected_output: vec![],
+            max_duration: Some(Duration::from_secs(5)),
+        };
+
+        let err = run_fixture(&fixture).await.unwrap_err();
+        assert!(err.reason.contains("expected exit code 0"));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_reports_stream_mismatch() {
+        let fixture = Fixture {
+            name: "stderr_pattern".to_string(),
+            command: "python3".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "import sys; sys.stderr.write('warning: low memory\\n')".to_string(),
+            ],
+            input: json!({}),
+            expected_exit_code: 0,
+            expected_output: vec![(Stream::Stderr, r"^error:".to_string())],
+            max_duration: Some(Duration::from_secs(5)),
+        };
+
+        let err = run_fixture(&fixture).await.unwrap_err();
+        assert!(err.reason.contains("did not match"));
+    }
+
+    #[test]
+    fn test_load_fixture_parses_json_spec() {
+        let spec = r#"{
+            "name": "echo_stdout",
+            "command": "python3",
+            "args": ["-c", "print('hi')"],
+            "input": {"message": "hello fixture"},
+            "expected_exit_code": 0,
+            "expected_output": {"stdout": "^hi$"},
+            "max_duration_secs": 5
+        }"#;
+
+        let fixture = load_fixture(spec).unwrap();
+        assert_eq!(fixture.name, "echo_stdout");
+        assert_eq!(fixture.max_duration, Some(Duration::from_secs(5)));
+        assert_eq!(
+            fixture.expected_output,
+            vec![(Stream::Stdout, "^hi$".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_load_fixture_accepts_multiple_patterns_per_stream() {
+        let spec = r#"{
+            "name": "multi_pattern",
+            "command": "python3",
+            "expected_output": {"stderr": ["^warn:", "low memory$"]}
+        }"#;
+
+        let fixture = load_fixture(spec).unwrap();
+        assert_eq!(
+            fixture.expected_output,
+            vec![
+                (Stream::Stderr, "^warn:".to_string()),
+                (Stream::Stderr, "low memory$".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_fixtures_runs_declared_array() {
+        let spec = r#"[{
+            "name": "echo_stdout",
+            "command": "python3",
+            "args": ["-c", "import json, sys; data=json.load(sys.stdin); print(data['message'])"],
+            "input": {"message": "hello fixture"},
+            "expected_output": {"stdout": "^hello fixture$"},
+            "max_duration_secs": 5
+        }]"#;
+
+        let fixtures = load_fixtures(spec).unwrap();
+        assert_eq!(fixtures.len(), 1);
+        assert!(run_fixture(&fixtures[0]).await.is_ok());
+    }
+
+    #[test]
+    fn test_load_fixture_file_reads_checked_in_spec() {
+        let fixture = load_fixture_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/codexify_success.json"
+        ))
+        .unwrap();
+        assert_eq!(fixture.name, "codexify_success");
+    }
+}
@@ -3,14 +3,24 @@
 //! A bridge layer connecting Python CLI tools to the Rust agent runtime.
 //! Provides typed APIs for spawning subprocesses and handling JSON I/O.
 
+pub mod artifact;
+pub mod bench;
 pub mod codexify;
 pub mod ritual_engine;
+pub mod fixtures;
 pub mod manifest;
+pub mod runner;
 pub mod subprocess;
+pub mod transport;
+pub mod worker_pool;
 
+use artifact::Artifact;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
+use transport::TransportKind;
+use worker_pool::WorkerPool;
 
 #[derive(Error, Debug)]
 pub enum BridgeError {
@@ -31,6 +41,9 @@ pub enum BridgeError {
     
     #[error("Tool not found: {0}")]
     ToolNotFound(String),
+
+    #[error("Transport error: {0}")]
+    Transport(String),
 }
 
 pub type BridgeResult<T> = Result<T, BridgeError>;
@@ -42,6 +55,10 @@ pub struct CapabilityResult<T> {
     pub data: Option<T>,
     pub error: Option<String>,
     pub metadata: ResponseMetadata,
+    /// Files the tool run produced as a side effect, populated when the
+    /// call's `SubprocessConfig::artifact_dir` was set. Empty otherwise.
+    #[serde(default)]
+    pub artifacts: Vec<Artifact>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,6 +81,7 @@ impl<T> CapabilityResult<T> {
                 timestamp: chrono::Utc::now(),
                 version: version.to_string(),
             },
+            artifacts: Vec::new(),
         }
     }
 
@@ -78,8 +96,15 @@ impl<T> CapabilityResult<T> {
                 timestamp: chrono::Utc::now(),
                 version: version.to_string(),
             },
+            artifacts: Vec::new(),
         }
     }
+
+    /// Attach artifacts collected from this call's `artifact_dir`.
+    pub fn with_artifacts(mut self, artifacts: Vec<Artifact>) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
 }
 
 /// Common configuration for subprocess execution
@@ -89,6 +114,16 @@ pub struct SubprocessConfig {
     pub working_dir: Option<String>,
     pub env_vars: std::collections::HashMap<String, String>,
     pub log_io: bool,
+    /// Optional worker pool to dispatch this call through instead of
+    /// spawning a fresh process. Existing callers that leave this `None`
+    /// keep the spawn-per-call behavior.
+    pub pool: Option<Arc<WorkerPool>>,
+    /// Where the command actually runs. Defaults to the local machine.
+    pub transport: TransportKind,
+    /// Directory to create before spawning and export to the child as
+    /// `ARTIFACT_DIR`. When set, a successful run's output files are
+    /// collected into `CapabilityResult::artifacts`.
+    pub artifact_dir: Option<String>,
 }
 
 impl Default for SubprocessConfig {
@@ -98,10 +133,18 @@ impl Default for SubprocessConfig {
             working_dir: None,
             env_vars: std::collections::HashMap::new(),
             log_io: true,
+            pool: None,
+            transport: TransportKind::default(),
+            artifact_dir: None,
         }
     }
 }
 
 /// Re-export commonly used items
-pub use subprocess::{spawn_subprocess, SubprocessConfig};
+pub use artifact::read_artifact;
+pub use subprocess::{spawn_subprocess, spawn_subprocess_streaming, StreamFrame};
+pub use fixtures::{run_fixture, Fixture, FixtureFailure, Stream};
 pub use manifest::{Manifest, load_manifest};
+pub use runner::run_tool;
+pub use transport::{LocalTransport, RemoteTransport, Transport, TransportOutput};
+pub use worker_pool::{PooledWorker, WorkerPoolConfig};
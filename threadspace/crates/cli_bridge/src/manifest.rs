@@ -1,3 +1,4 @@
+use crate::BridgeResult;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
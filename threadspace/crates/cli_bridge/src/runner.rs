@@ -0,0 +1,246 @@
+//! Manifest-driven generic tool runner.
+//!
+//! `run_codexify` and `run_ritual_engine` hardcode their python path,
+//! timeout, and skip validation against the tool's [`Manifest`]. `run_tool`
+//! drives any manifest-described tool instead: it validates `input` against
+//! `manifest.inputs` (required fields, defaults, type matching), executes
+//! the tool via `manifest.entry_point` / `manifest.timeout_sec`, and
+//! validates the returned JSON against `manifest.outputs` before handing
+//! back a [`CapabilityResult`]. Dropping a new manifest JSON file into
+//! `manifests/` is enough to make a Python tool pluggable, with no new Rust
+//! code required.
+
+use crate::manifest::{InputSchema, Manifest};
+use crate::{BridgeError, BridgeResult, CapabilityResult, SubprocessConfig};
+use serde_json::{Map, Value};
+use std::time::{Duration, Instant};
+
+/// Run `manifest`'s tool against `input`, validating both ends against the
+/// manifest's schemas.
+pub async fn run_tool(
+    manifest: &Manifest,
+    input: Value,
+    config: Option<SubprocessConfig>,
+) -> BridgeResult<CapabilityResult<Value>> {
+    let start_time = Instant::now();
+    let validated_input = validate_input(manifest, input)?;
+
+    let config = config.unwrap_or_else(|| SubprocessConfig {
+        timeout: Duration::from_secs(manifest.timeout_sec),
+        ..Default::default()
+    });
+
+    let result = crate::spawn_subprocess::<Value, Value>(
+        "python3",
+        &[manifest.entry_point.as_str()],
+        &validated_input,
+        &config,
+    )
+    .await;
+
+    let duration = start_time.elapsed();
+
+    match result {
+        Ok(output) => {
+            validate_output(manifest, &output)?;
+            let mut capability_result =
+                CapabilityResult::success(output, &manifest.name, duration, &manifest.version);
+            if let Some(artifact_dir) = &config.artifact_dir {
+                match crate::artifact::collect_artifacts(artifact_dir) {
+                    Ok(artifacts) => capability_result = capability_result.with_artifacts(artifacts),
+                    Err(e) => {
+                        return Ok(CapabilityResult::error(
+                            e.to_string(),
+                            &manifest.name,
+                            duration,
+                            &manifest.version,
+                        ))
+                    }
+                }
+            }
+            Ok(capability_result)
+        }
+        Err(e) => Ok(CapabilityResult::error(
+            e.to_string(),
+            &manifest.name,
+            duration,
+            &manifest.version,
+        )),
+    }
+}
+
+/// Check `input` against `manifest.inputs`, filling in declared defaults
+/// for absent optional fields.
+fn validate_input(manifest: &Manifest, input: Value) -> BridgeResult<Value> {
+    let mut fields = match input {
+        Value::Object(fields) => fields,
+        _ => return Err(BridgeError::InvalidOutput("input must be a JSON object".to_string())),
+    };
+
+    for (name, schema) in &manifest.inputs {
+        match fields.get(name) {
+            Some(value) => check_type(name, &schema.r#type, value)?,
+            None => fill_missing_input(&mut fields, name, schema)?,
+        }
+    }
+
+    Ok(Value::Object(fields))
+}
+
+fn fill_missing_input(
+    fields: &mut Map<String, Value>,
+    name: &str,
+    schema: &InputSchema,
+) -> BridgeResult<()> {
+    if let Some(default) = &schema.default {
+        fields.insert(name.to_string(), default.clone());
+        Ok(())
+    } else if schema.required {
+        Err(BridgeError::InvalidOutput(format!(
+            "missing required input field '{}'",
+            name
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check the tool's returned JSON object against `manifest.outputs`.
+fn validate_output(manifest: &Manifest, output: &Value) -> BridgeResult<()> {
+    let fields = output
+        .as_object()
+        .ok_or_else(|| BridgeError::InvalidOutput("output must be a JSON object".to_string()))?;
+
+    for (name, schema) in &manifest.outputs {
+        match fields.get(name) {
+            Some(value) => check_type(name, &schema.r#type, value)?,
+            None => {
+                return Err(BridgeError::InvalidOutput(format!(
+                    "missing required output field '{}'",
+                    name
+                )))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_type(field: &str, expected: &str, value: &Value) -> BridgeResult<()> {
+    let matches = match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        // Unknown schema types are not rejected; the manifest controls what vocabulary it uses.
+        _ => true,
+    };
+
+    if matches {
+        Ok(())
+    } else {
+        Err(BridgeError::InvalidOutput(format!(
+            "field '{}' does not match expected type '{}'",
+            field, expected
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::generate_codexify_manifest;
+    use serde_json::json;
+    use std::io::Write;
+
+    #[tokio::test]
+    async fn test_run_tool_folds_artifact_collection_failure_into_capability_error() {
+        // A tool that succeeds but then replaces its own ARTIFACT_DIR with a
+        // plain file before exiting, so the post-run artifact scan fails.
+        let mut script = tempfile::NamedTempFile::with_suffix(".py").unwrap();
+        writeln!(
+            script,
+            r#"
+import json, os, sys
+artifact_dir = os.environ["ARTIFACT_DIR"]
+os.rmdir(artifact_dir)
+with open(artifact_dir, "w") as f:
+    f.write("not a directory")
+print(json.dumps({{}}))
+"#
+        )
+        .unwrap();
+
+        let manifest = Manifest {
+            name: "artifact_race".to_string(),
+            version: "1.0.0".to_string(),
+            description: "test tool".to_string(),
+            language: "python".to_string(),
+            entry_point: script.path().to_string_lossy().to_string(),
+            capabilities: vec![],
+            schema: None,
+            timeout_sec: 10,
+            requirements: Default::default(),
+            inputs: Default::default(),
+            outputs: Default::default(),
+        };
+
+        let artifact_dir = tempfile::tempdir().unwrap();
+        let config = SubprocessConfig {
+            artifact_dir: Some(artifact_dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+
+        let result = run_tool(&manifest, json!({}), Some(config)).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.is_some());
+    }
+
+    #[test]
+    fn test_validate_input_fills_default_and_passes_required() {
+        let manifest = generate_codexify_manifest();
+        let input = json!({ "file_path": "/tmp/example.txt" });
+
+        let validated = validate_input(&manifest, input).unwrap();
+        assert_eq!(validated["file_path"], "/tmp/example.txt");
+        assert_eq!(validated["tags"], json!([]));
+    }
+
+    #[test]
+    fn test_validate_input_rejects_missing_required_field() {
+        let manifest = generate_codexify_manifest();
+        let input = json!({ "tags": ["a"] });
+
+        let err = validate_input(&manifest, input).unwrap_err();
+        assert!(matches!(err, BridgeError::InvalidOutput(msg) if msg.contains("file_path")));
+    }
+
+    #[test]
+    fn test_validate_input_rejects_type_mismatch() {
+        let manifest = generate_codexify_manifest();
+        let input = json!({ "file_path": 123 });
+
+        let err = validate_input(&manifest, input).unwrap_err();
+        assert!(matches!(err, BridgeError::InvalidOutput(msg) if msg.contains("file_path")));
+    }
+
+    #[test]
+    fn test_validate_output_rejects_missing_field() {
+        let manifest = generate_codexify_manifest();
+        let output = json!({ "node_id": "abc" });
+
+        let err = validate_output(&manifest, &output).unwrap_err();
+        assert!(matches!(err, BridgeError::InvalidOutput(msg) if msg.contains("summary")));
+    }
+
+    #[test]
+    fn test_validate_output_accepts_matching_schema() {
+        let manifest = generate_codexify_manifest();
+        let output = json!({ "node_id": "abc", "summary": "a summary" });
+
+        assert!(validate_output(&manifest, &output).is_ok());
+    }
+}
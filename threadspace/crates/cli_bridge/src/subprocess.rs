@@ -1,10 +1,27 @@
+use crate::transport::TransportKind;
 use crate::{BridgeError, BridgeResult, SubprocessConfig};
 use serde::{Deserialize, Serialize};
-use std::process::{Command, Stdio};
-use std::time::Instant;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command as TokioCommand;
-use tracing::{debug, error, info};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+/// Capacity of the channel returned by [`spawn_subprocess_streaming`].
+const STREAM_CHANNEL_CAPACITY: usize = 32;
+
+/// A single NDJSON frame emitted by a long-running subprocess.
+///
+/// Tools that stream progress write one of these, newline-delimited, per
+/// line of stdout. A `result` frame is terminal: it carries the typed
+/// output and ends the stream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StreamFrame<O> {
+    Log { line: String },
+    Progress { pct: f64 },
+    Result { data: O },
+}
 
 /// Spawn a subprocess and handle JSON I/O
 pub async fn spawn_subprocess<I, O>(
@@ -17,72 +34,270 @@ where
     I: Serialize,
     O: for<'de> Deserialize<'de>,
 {
-    let start_time = Instant::now();
-    
+    // If a worker pool is configured, dispatch through a persistent worker
+    // instead of spawning a fresh process for this call. Worker dispatch
+    // doesn't thread an artifact directory through to the pooled process,
+    // so reject the combination rather than silently reporting `artifacts:
+    // []` for a call that never got `ARTIFACT_DIR` exported.
+    if let Some(pool) = &config.pool {
+        if config.artifact_dir.is_some() {
+            return Err(BridgeError::InvalidOutput(
+                "SubprocessConfig.pool and SubprocessConfig.artifact_dir cannot be combined: \
+                 pooled workers don't receive a per-call ARTIFACT_DIR"
+                    .to_string(),
+            ));
+        }
+        let mut worker = pool.acquire().await?;
+        // Pooled dispatch skips the transport layer entirely, so nothing
+        // else enforces config.timeout here; a worker that never responds
+        // (or never closes) would otherwise hang the caller forever.
+        return match tokio::time::timeout(config.timeout, worker.call(input)).await {
+            Ok(result) => result,
+            Err(_) => {
+                worker.retire();
+                Err(BridgeError::Timeout {
+                    duration: config.timeout,
+                })
+            }
+        };
+    }
+
+    // `artifact_dir` is created and later scanned on this machine, but a
+    // TransportKind::Ssh child writes ARTIFACT_DIR on the *remote* host —
+    // so collect_artifacts would silently come back empty (or reflect
+    // stale unrelated local files) instead of the tool's real output.
+    // Reject the combination rather than return wrong data, matching how
+    // `pool` + `artifact_dir` is rejected above.
+    if config.artifact_dir.is_some() && !matches!(config.transport, TransportKind::Local) {
+        return Err(BridgeError::InvalidOutput(
+            "SubprocessConfig.artifact_dir and a non-local transport cannot be combined: \
+             artifacts would be written on the remote host but collected locally"
+                .to_string(),
+        ));
+    }
+
     // Serialize input to JSON
     let input_json = serde_json::to_string(input)?;
     if config.log_io {
         debug!("Input JSON: {}", input_json);
     }
 
-    // Build command
+    info!("Spawning subprocess: {} {}", command, args.join(" "));
+
+    // If an artifact directory is configured, create it and export it to
+    // the child so a tool like codexify knows where to write generated
+    // files for us to collect afterward.
+    let mut effective_config = config.clone();
+    if let Some(artifact_dir) = &config.artifact_dir {
+        crate::artifact::prepare_artifact_dir(artifact_dir)?;
+        effective_config
+            .env_vars
+            .insert(crate::artifact::ARTIFACT_DIR_ENV.to_string(), artifact_dir.clone());
+    }
+
+    // Run the command through whichever transport this config selects
+    // (local by default, or e.g. SSH for a remote agent host).
+    let transport = effective_config.transport.build();
+    let output = transport
+        .execute(command, args, &input_json, &effective_config)
+        .await?;
+
+    // Check exit status
+    if output.exit_code != Some(0) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        error!("Process failed with stderr: {}", stderr);
+        return Err(BridgeError::ProcessFailed(output.exit_code.unwrap_or(-1)));
+    }
+
+    // Parse output JSON
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if config.log_io {
+        debug!("Output JSON: {}", stdout);
+    }
+
+    let parsed: O = serde_json::from_str(&stdout)?;
+    Ok(parsed)
+}
+
+/// Spawn a subprocess that streams newline-delimited JSON frames back as it runs.
+///
+/// Unlike [`spawn_subprocess`], which blocks until the child exits, this keeps
+/// stdin/stdout piped and forwards each parsed [`StreamFrame`] through the
+/// returned channel as soon as its line arrives. This is what a long-running
+/// `ritual_engine` call should use so logs and progress surface incrementally
+/// instead of only after the process exits.
+///
+/// The configured timeout is applied between frames (an idle timeout) rather
+/// than across the whole process lifetime; a child that keeps emitting
+/// frames can run indefinitely, but one that goes silent is killed.
+pub async fn spawn_subprocess_streaming<I, O>(
+    command: &str,
+    args: &[&str],
+    input: &I,
+    config: &SubprocessConfig,
+) -> BridgeResult<mpsc::Receiver<BridgeResult<StreamFrame<O>>>>
+where
+    I: Serialize,
+    O: for<'de> Deserialize<'de> + Send + 'static,
+{
+    // This path always spawns a bare local `TokioCommand` and reads frames
+    // straight off its stdout; it doesn't go through `Transport`, doesn't
+    // dispatch through a `WorkerPool`, and never exports `ARTIFACT_DIR`.
+    // Reject a config that asks for any of those instead of silently
+    // downgrading to local/non-pooled/non-artifact execution, matching how
+    // `spawn_subprocess` rejects `pool` + `artifact_dir` rather than
+    // dropping one of them.
+    if !matches!(config.transport, TransportKind::Local) {
+        return Err(BridgeError::InvalidOutput(
+            "spawn_subprocess_streaming only supports TransportKind::Local; remote \
+             transports are not wired into the streaming path"
+                .to_string(),
+        ));
+    }
+    if config.pool.is_some() {
+        return Err(BridgeError::InvalidOutput(
+            "spawn_subprocess_streaming does not support SubprocessConfig.pool: pooled \
+             workers are not wired into the streaming path"
+                .to_string(),
+        ));
+    }
+    if config.artifact_dir.is_some() {
+        return Err(BridgeError::InvalidOutput(
+            "spawn_subprocess_streaming does not support SubprocessConfig.artifact_dir: \
+             the streaming path never exports ARTIFACT_DIR to the child"
+                .to_string(),
+        ));
+    }
+
+    let input_json = serde_json::to_string(input)?;
+    if config.log_io {
+        debug!("Input JSON: {}", input_json);
+    }
+
     let mut cmd = TokioCommand::new(command);
     cmd.args(args)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    // Set working directory if specified
     if let Some(ref dir) = config.working_dir {
         cmd.current_dir(dir);
     }
 
-    // Set environment variables
     for (key, value) in &config.env_vars {
         cmd.env(key, value);
     }
 
-    info!("Spawning subprocess: {} {}", command, args.join(" "));
+    info!("Spawning streaming subprocess: {} {}", command, args.join(" "));
 
-    // Spawn the process
     let mut child = cmd.spawn().map_err(BridgeError::Io)?;
 
-    // Write input to stdin
     if let Some(stdin) = child.stdin.take() {
         let mut stdin = tokio::io::BufWriter::new(stdin);
         stdin.write_all(input_json.as_bytes()).await?;
         stdin.flush().await?;
     }
 
-    // Wait for process with timeout
-    let timeout_duration = config.timeout;
-    let output = match tokio::time::timeout(timeout_duration, child.wait_with_output()).await {
-        Ok(result) => result.map_err(BridgeError::Io)?,
-        Err(_) => {
-            child.kill().await.ok();
-            return Err(BridgeError::Timeout { duration: timeout_duration });
-        }
-    };
-
-    let duration = start_time.elapsed();
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| BridgeError::InvalidOutput("child has no stdout".to_string()))?;
 
-    // Check exit status
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        error!("Process failed with stderr: {}", stderr);
-        return Err(BridgeError::ProcessFailed(
-            output.status.code().unwrap_or(-1),
-        ));
+    // Drain stderr concurrently with the stdout line loop below. Left
+    // unread, a chatty child (e.g. a Python traceback) can fill the OS pipe
+    // buffer and block the process on its next stderr write, stalling the
+    // very long-running tool this function exists to serve.
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(async move {
+            let mut stderr_lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = stderr_lines.next_line().await {
+                warn!("Streaming subprocess stderr: {}", line);
+            }
+        });
     }
 
-    // Parse output JSON
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    if config.log_io {
-        debug!("Output JSON: {}", stdout);
-    }
+    let idle_timeout = config.timeout;
+    let (tx, rx) = mpsc::channel(STREAM_CHANNEL_CAPACITY);
+    let log_io = config.log_io;
 
-    let parsed: O = serde_json::from_str(&stdout)?;
-    Ok(parsed)
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(stdout).lines();
+        let mut sent_result = false;
+
+        loop {
+            match tokio::time::timeout(idle_timeout, lines.next_line()).await {
+                Ok(Ok(Some(line))) => {
+                    if log_io {
+                        debug!("Stream frame: {}", line);
+                    }
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    match serde_json::from_str::<StreamFrame<O>>(&line) {
+                        Ok(frame) => {
+                            let is_result = matches!(frame, StreamFrame::Result { .. });
+                            if tx.send(Ok(frame)).await.is_err() {
+                                // Receiver gave up (dropped the channel, e.g. it's
+                                // racing the stream against its own shutdown). Kill
+                                // the child instead of leaking it and this task in
+                                // `child.wait()` until it decides to exit on its own.
+                                warn!("Streaming subprocess receiver dropped, killing child");
+                                child.kill().await.ok();
+                                return;
+                            }
+                            if is_result {
+                                sent_result = true;
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            let _ = tx.send(Err(BridgeError::Json(e))).await;
+                            break;
+                        }
+                    }
+                }
+                Ok(Ok(None)) => break,
+                Ok(Err(e)) => {
+                    let _ = tx.send(Err(BridgeError::Io(e))).await;
+                    break;
+                }
+                Err(_) => {
+                    error!("Streaming subprocess went idle for {:?}, killing", idle_timeout);
+                    child.kill().await.ok();
+                    let _ = tx
+                        .send(Err(BridgeError::Timeout {
+                            duration: idle_timeout,
+                        }))
+                        .await;
+                    return;
+                }
+            }
+        }
+
+        // Stdout closed (or ended on a non-terminal frame) without ever
+        // producing a `Result` frame. That's ambiguous on its own -- the
+        // child may simply have finished writing -- so fold in its exit
+        // status the same way the blocking `spawn_subprocess` path does:
+        // a non-zero exit means the tool failed, not that it quietly
+        // forgot the terminal frame.
+        if !sent_result {
+            match child.wait().await {
+                Ok(status) if !status.success() => {
+                    let _ = tx
+                        .send(Err(BridgeError::ProcessFailed(status.code().unwrap_or(-1))))
+                        .await;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    let _ = tx.send(Err(BridgeError::Io(e))).await;
+                }
+            }
+        } else {
+            child.wait().await.ok();
+        }
+    });
+
+    Ok(rx)
 }
 
 /// Synchronous version for blocking contexts
@@ -104,6 +319,7 @@ where
 mod tests {
     use super::*;
     use serde::{Deserialize, Serialize};
+    use std::time::Duration;
 
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
     struct TestInput {
@@ -115,6 +331,27 @@ mod tests {
         response: String,
     }
 
+    #[tokio::test]
+    async fn test_rejects_artifact_dir_with_remote_transport() {
+        let config = SubprocessConfig {
+            artifact_dir: Some("/tmp/artifacts".to_string()),
+            transport: TransportKind::Ssh {
+                host: "example.com".to_string(),
+                user: "agent".to_string(),
+                identity: None,
+            },
+            ..Default::default()
+        };
+        let input = TestInput {
+            message: "hello".to_string(),
+        };
+
+        let result =
+            spawn_subprocess::<TestInput, TestOutput>("python3", &[], &input, &config).await;
+
+        assert!(matches!(result, Err(BridgeError::InvalidOutput(_))));
+    }
+
     #[tokio::test]
     async fn test_echo_subprocess() {
         let config = SubprocessConfig::default();
@@ -134,4 +371,172 @@ mod tests {
         let output = result.unwrap();
         assert_eq!(output.response, "hello");
     }
+
+    // Answers the pool's ping frame so it passes `acquire`'s health check,
+    // but never responds to a real call — simulating a worker wedged mid-work.
+    const HUNG_WORKER_SCRIPT: &str = r#"
+import json, sys, time
+for line in sys.stdin:
+    line = line.strip()
+    if not line:
+        continue
+    req = json.loads(line)
+    payload = req.get("payload", {})
+    if isinstance(payload, dict) and payload.get("__ping__"):
+        print(json.dumps({"id": req["id"], "result": {"pong": True}}))
+        sys.stdout.flush()
+    else:
+        time.sleep(9999)
+"#;
+
+    #[tokio::test]
+    async fn test_pooled_dispatch_times_out_on_hung_worker() {
+        use crate::worker_pool::{WorkerPool, WorkerPoolConfig};
+
+        let pool_config = WorkerPoolConfig {
+            args: vec!["-c".to_string(), HUNG_WORKER_SCRIPT.to_string()],
+            max_size: 1,
+            ..Default::default()
+        };
+        let pool = WorkerPool::new(pool_config).await.unwrap();
+
+        let config = SubprocessConfig {
+            timeout: Duration::from_millis(200),
+            pool: Some(pool),
+            ..Default::default()
+        };
+        let input = TestInput {
+            message: "hello".to_string(),
+        };
+
+        let result =
+            spawn_subprocess::<TestInput, TestOutput>("python3", &[], &input, &config).await;
+
+        assert!(matches!(result, Err(BridgeError::Timeout { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_subprocess_emits_frames_then_result() {
+        let config = SubprocessConfig::default();
+        let input = TestInput {
+            message: "hello".to_string(),
+        };
+
+        // Emit a log frame, a progress frame, then the terminal result frame.
+        let script = r#"
+import json, sys
+data = json.load(sys.stdin)
+print(json.dumps({"kind": "log", "line": "starting"}))
+print(json.dumps({"kind": "progress", "pct": 50.0}))
+print(json.dumps({"kind": "result", "data": {"response": data["message"]}}))
+"#;
+
+        let mut rx = spawn_subprocess_streaming::<TestInput, TestOutput>(
+            "python3",
+            &["-c", script],
+            &input,
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let mut frames = Vec::new();
+        while let Some(frame) = rx.recv().await {
+            frames.push(frame.unwrap());
+        }
+
+        assert_eq!(frames.len(), 3);
+        assert!(matches!(&frames[0], StreamFrame::Log { line } if line == "starting"));
+        assert!(matches!(&frames[1], StreamFrame::Progress { pct } if *pct == 50.0));
+        match &frames[2] {
+            StreamFrame::Result { data } => assert_eq!(data.response, "hello"),
+            other => panic!("expected result frame, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_subprocess_reports_process_failed_without_result_frame() {
+        let config = SubprocessConfig::default();
+        let input = TestInput {
+            message: "hello".to_string(),
+        };
+
+        // Emit one log frame, then exit non-zero before ever writing a
+        // result frame.
+        let script = r#"
+import json, sys
+print(json.dumps({"kind": "log", "line": "starting"}))
+sys.exit(1)
+"#;
+
+        let mut rx = spawn_subprocess_streaming::<TestInput, TestOutput>(
+            "python3",
+            &["-c", script],
+            &input,
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let first = rx.recv().await.unwrap().unwrap();
+        assert!(matches!(&first, StreamFrame::Log { line } if line == "starting"));
+
+        let second = rx.recv().await.unwrap();
+        assert!(matches!(second, Err(BridgeError::ProcessFailed(1))));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_streaming_subprocess_kills_child_when_receiver_dropped() {
+        use std::fs;
+        use tempfile::NamedTempFile;
+
+        let counter_file = NamedTempFile::new().unwrap();
+        let counter_path = counter_file.path().to_str().unwrap().to_string();
+        fs::write(&counter_path, "0").unwrap();
+
+        let config = SubprocessConfig::default();
+        let input = TestInput {
+            message: counter_path.clone(),
+        };
+
+        // A "forever" tool: it keeps emitting progress frames and bumping a
+        // counter on disk until something kills it.
+        let script = r#"
+import json, sys, time
+data = json.load(sys.stdin)
+i = 0
+while True:
+    with open(data["message"], "w") as f:
+        f.write(str(i))
+    print(json.dumps({"kind": "progress", "pct": float(i)}))
+    sys.stdout.flush()
+    i += 1
+    time.sleep(0.05)
+"#;
+
+        let mut rx = spawn_subprocess_streaming::<TestInput, TestOutput>(
+            "python3",
+            &["-c", script],
+            &input,
+            &config,
+        )
+        .await
+        .unwrap();
+
+        // Take one frame to be sure the child is actually running, then give
+        // up on the stream instead of draining it to the end.
+        rx.recv().await.unwrap().unwrap();
+        drop(rx);
+
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        let count_after_drop: i64 = fs::read_to_string(&counter_path).unwrap().parse().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        let count_later: i64 = fs::read_to_string(&counter_path).unwrap().parse().unwrap();
+
+        assert_eq!(
+            count_after_drop, count_later,
+            "child kept running after the stream receiver was dropped"
+        );
+    }
 }
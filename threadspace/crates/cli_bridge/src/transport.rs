@@ -0,0 +1,319 @@
+//! Pluggable execution transport for subprocess calls.
+//!
+//! `spawn_subprocess` used to be hardwired to [`tokio::process::Command`] on
+//! the local machine. [`Transport`] abstracts "spawn a command, pipe JSON
+//! stdin, collect stdout/stderr, enforce a timeout, and kill on expiry" so
+//! the same codexify/ritual_engine entry-point paths, env vars, and working
+//! directory can run on a remote host instead — useful when the GPU/LLM
+//! backend lives on a different machine than the agent runtime.
+
+use crate::{BridgeError, BridgeResult, SubprocessConfig};
+use std::future::Future;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command as TokioCommand;
+
+/// Raw output of running a command through a [`Transport`], before the
+/// caller parses stdout as JSON.
+#[derive(Debug, Clone)]
+pub struct TransportOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub exit_code: Option<i32>,
+}
+
+/// Which [`Transport`] a [`SubprocessConfig`] should use.
+#[derive(Debug, Clone, Default)]
+pub enum TransportKind {
+    #[default]
+    Local,
+    Ssh {
+        host: String,
+        user: String,
+        identity: Option<String>,
+    },
+}
+
+impl TransportKind {
+    /// Build the concrete [`Transport`] this variant describes.
+    pub fn build(&self) -> Box<dyn Transport> {
+        match self {
+            TransportKind::Local => Box::new(LocalTransport),
+            TransportKind::Ssh {
+                host,
+                user,
+                identity,
+            } => Box::new(RemoteTransport {
+                host: host.clone(),
+                user: user.clone(),
+                identity: identity.clone(),
+            }),
+        }
+    }
+}
+
+/// Where a tool's subprocess actually runs.
+pub trait Transport: Send + Sync {
+    /// Run `command args` with `input_json` piped to stdin, enforcing
+    /// `config.timeout` and killing the process if it's exceeded.
+    fn execute<'a>(
+        &'a self,
+        command: &'a str,
+        args: &'a [&'a str],
+        input_json: &'a str,
+        config: &'a SubprocessConfig,
+    ) -> Pin<Box<dyn Future<Output = BridgeResult<TransportOutput>> + Send + 'a>>;
+}
+
+/// Runs the command on this machine. This is the transport `spawn_subprocess`
+/// has always used.
+#[derive(Debug, Clone, Default)]
+pub struct LocalTransport;
+
+impl Transport for LocalTransport {
+    fn execute<'a>(
+        &'a self,
+        command: &'a str,
+        args: &'a [&'a str],
+        input_json: &'a str,
+        config: &'a SubprocessConfig,
+    ) -> Pin<Box<dyn Future<Output = BridgeResult<TransportOutput>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut cmd = TokioCommand::new(command);
+            cmd.args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+
+            if let Some(ref dir) = config.working_dir {
+                cmd.current_dir(dir);
+            }
+            for (key, value) in &config.env_vars {
+                cmd.env(key, value);
+            }
+
+            run_piped(cmd, input_json, config.timeout).await
+        })
+    }
+}
+
+/// Runs the command on a remote host over SSH.
+#[derive(Debug, Clone)]
+pub struct RemoteTransport {
+    pub host: String,
+    pub user: String,
+    pub identity: Option<String>,
+}
+
+impl Transport for RemoteTransport {
+    fn execute<'a>(
+        &'a self,
+        command: &'a str,
+        args: &'a [&'a str],
+        input_json: &'a str,
+        config: &'a SubprocessConfig,
+    ) -> Pin<Box<dyn Future<Output = BridgeResult<TransportOutput>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut cmd = TokioCommand::new("ssh");
+            if let Some(identity) = &self.identity {
+                cmd.arg("-i").arg(identity);
+            }
+            cmd.arg(format!("{}@{}", self.user, self.host));
+
+            // OpenSSH joins every argument after the destination with spaces
+            // and hands the result to the remote shell as one command
+            // string, so each token must be shell-quoted individually —
+            // otherwise a space or shell metacharacter in an arg or env
+            // value (e.g. an artifact_dir path, or a crafted env value)
+            // either gets mis-split or executes as remote shell code.
+            //
+            // SubprocessConfig::env_vars describes the remote process's
+            // environment, not the local ssh client's, so prefix the single
+            // remote invocation with `env NAME=value ...` instead of
+            // appending a second, separate command after it.
+            let remote_command = build_remote_command(command, args, config);
+            cmd.arg(remote_command);
+
+            cmd.stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+
+            run_piped(cmd, input_json, config.timeout)
+                .await
+                .map_err(|e| match e {
+                    BridgeError::Io(io_err) => BridgeError::Transport(format!(
+                        "ssh to {}@{} failed: {}",
+                        self.user, self.host, io_err
+                    )),
+                    other => other,
+                })
+        })
+    }
+}
+
+/// Quote `token` for safe inclusion in a POSIX shell command line, the way
+/// [`RemoteTransport::execute`] must build the single string `ssh` hands to
+/// the remote shell. Wraps in single quotes and escapes any embedded single
+/// quote as `'\''`.
+fn shell_quote(token: &str) -> String {
+    format!("'{}'", token.replace('\'', "'\\''"))
+}
+
+/// Build the single command string [`RemoteTransport::execute`] hands to
+/// `ssh` for the remote shell to run: an optional `cd <working_dir> &&`
+/// prefix, an optional `env NAME=value ...` prefix for `config.env_vars`,
+/// then `command` and `args`, all individually shell-quoted.
+fn build_remote_command(command: &str, args: &[&str], config: &SubprocessConfig) -> String {
+    let mut parts = Vec::new();
+    if let Some(dir) = &config.working_dir {
+        parts.push(format!("cd {} &&", shell_quote(dir)));
+    }
+    if !config.env_vars.is_empty() {
+        parts.push("env".to_string());
+        for (key, value) in &config.env_vars {
+            parts.push(shell_quote(&format!("{}={}", key, value)));
+        }
+    }
+    parts.push(shell_quote(command));
+    for arg in args {
+        parts.push(shell_quote(arg));
+    }
+    parts.join(" ")
+}
+
+/// Spawn `cmd`, write `input_json` to its stdin, and collect its output,
+/// killing the process if `timeout` elapses first.
+async fn run_piped(
+    mut cmd: TokioCommand,
+    input_json: &str,
+    timeout: Duration,
+) -> BridgeResult<TransportOutput> {
+    let mut child = cmd.spawn().map_err(BridgeError::Io)?;
+
+    if let Some(stdin) = child.stdin.take() {
+        let mut stdin = tokio::io::BufWriter::new(stdin);
+        stdin.write_all(input_json.as_bytes()).await?;
+        stdin.flush().await?;
+    }
+
+    let mut stdout_handle = child.stdout.take();
+    let mut stderr_handle = child.stderr.take();
+
+    let collect = async {
+        let read_stdout = async {
+            let mut buf = Vec::new();
+            if let Some(h) = stdout_handle.as_mut() {
+                h.read_to_end(&mut buf).await.ok();
+            }
+            buf
+        };
+        let read_stderr = async {
+            let mut buf = Vec::new();
+            if let Some(h) = stderr_handle.as_mut() {
+                h.read_to_end(&mut buf).await.ok();
+            }
+            buf
+        };
+        let (stdout, stderr) = tokio::join!(read_stdout, read_stderr);
+        let status = child.wait().await?;
+        Ok::<_, std::io::Error>((stdout, stderr, status))
+    };
+
+    match tokio::time::timeout(timeout, collect).await {
+        Ok(Ok((stdout, stderr, status))) => Ok(TransportOutput {
+            stdout,
+            stderr,
+            exit_code: status.code(),
+        }),
+        Ok(Err(e)) => Err(BridgeError::Io(e)),
+        Err(_) => {
+            child.kill().await.ok();
+            Err(BridgeError::Timeout { duration: timeout })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_transport_echoes_stdin() {
+        let transport = LocalTransport;
+        let config = SubprocessConfig::default();
+
+        let output = transport
+            .execute(
+                "python3",
+                &["-c", "import sys; sys.stdout.write(sys.stdin.read())"],
+                "hello",
+                &config,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(output.exit_code, Some(0));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_local_transport_kills_on_idle_timeout() {
+        let transport = LocalTransport;
+        let config = SubprocessConfig {
+            timeout: Duration::from_millis(50),
+            ..Default::default()
+        };
+
+        let result = transport
+            .execute("python3", &["-c", "import time; time.sleep(5)"], "", &config)
+            .await;
+
+        assert!(matches!(result, Err(BridgeError::Timeout { .. })));
+    }
+
+    #[test]
+    fn test_shell_quote_escapes_embedded_single_quotes() {
+        assert_eq!(shell_quote("hello"), "'hello'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote("a b"), "'a b'");
+    }
+
+    #[test]
+    fn test_build_remote_command_plain() {
+        let config = SubprocessConfig::default();
+        assert_eq!(
+            build_remote_command("python3", &["-c", "print(1)"], &config),
+            "'python3' '-c' 'print(1)'"
+        );
+    }
+
+    #[test]
+    fn test_build_remote_command_prefixes_cd_for_working_dir() {
+        let config = SubprocessConfig {
+            working_dir: Some("/tmp/a b".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            build_remote_command("python3", &[], &config),
+            "cd '/tmp/a b' && 'python3'"
+        );
+    }
+
+    #[test]
+    fn test_build_remote_command_orders_cd_before_env() {
+        let mut config = SubprocessConfig {
+            working_dir: Some("/srv/tool".to_string()),
+            ..Default::default()
+        };
+        config
+            .env_vars
+            .insert("ARTIFACT_DIR".to_string(), "/tmp/out".to_string());
+
+        assert_eq!(
+            build_remote_command("python3", &[], &config),
+            "cd '/srv/tool' && env 'ARTIFACT_DIR=/tmp/out' 'python3'"
+        );
+    }
+}
@@ -0,0 +1,520 @@
+//! Persistent pool of long-lived Python worker subprocesses.
+//!
+//! Spawning a fresh `python3` process per call pays the full interpreter and
+//! import cost (openai, chromadb, tiktoken) on every request. A [`WorkerPool`]
+//! keeps a handful of workers alive with stdin/stdout held open and dispatches
+//! requests over a framed NDJSON protocol tagged with a correlation `id`, so
+//! responses can be matched back to in-flight requests even if a worker
+//! answers them out of order. `SubprocessConfig::pool` lets existing callers
+//! opt into this without changing their call sites.
+
+use crate::{BridgeError, BridgeResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command as TokioCommand};
+use tokio::sync::{oneshot, Mutex, OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, error, warn};
+
+/// Configuration for a [`WorkerPool`].
+#[derive(Debug, Clone)]
+pub struct WorkerPoolConfig {
+    pub command: String,
+    pub args: Vec<String>,
+    pub max_size: usize,
+    pub ping_timeout: Duration,
+    pub working_dir: Option<String>,
+    pub env_vars: HashMap<String, String>,
+    /// How long a worker may sit idle before the background reaper retires
+    /// it. A busy pool never grows past `max_size`, but without this an
+    /// idle one sits at `max_size` long-lived interpreter processes
+    /// forever, even overnight.
+    pub idle_timeout: Duration,
+}
+
+impl Default for WorkerPoolConfig {
+    fn default() -> Self {
+        Self {
+            command: "python3".to_string(),
+            args: Vec::new(),
+            max_size: 4,
+            ping_timeout: Duration::from_secs(2),
+            working_dir: None,
+            env_vars: HashMap::new(),
+            idle_timeout: Duration::from_secs(300),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct WorkerRequest {
+    id: u64,
+    payload: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkerResponse {
+    id: u64,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>>;
+
+/// One persistent worker process and the task reading its responses.
+struct Worker {
+    child: Child,
+    stdin: ChildStdin,
+    pending: PendingMap,
+    alive: Arc<AtomicBool>,
+    reader_task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.reader_task.abort();
+        self.child.start_kill().ok();
+    }
+}
+
+/// A checked-out worker. Dispatch requests with [`PooledWorker::call`]; the
+/// worker is returned to the pool when this guard is dropped.
+pub struct PooledWorker {
+    pool: Arc<WorkerPool>,
+    worker: Option<Worker>,
+    /// Held for the lifetime of the checkout so the pool's total worker
+    /// count (idle + outstanding) never exceeds `max_size`. Released only
+    /// once the worker has been pushed back onto the idle queue, so a
+    /// waiting `acquire()` can't spawn an extra worker in the gap between
+    /// this guard dropping and the worker actually becoming idle again.
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl PooledWorker {
+    /// Send `payload` to the worker and await its matching response.
+    pub async fn call<I, O>(&mut self, payload: &I) -> BridgeResult<O>
+    where
+        I: Serialize,
+        O: for<'de> Deserialize<'de>,
+    {
+        let worker = self
+            .worker
+            .as_mut()
+            .expect("PooledWorker used after being returned");
+
+        let id = self.pool.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        worker.pending.lock().await.insert(id, tx);
+
+        let request = WorkerRequest {
+            id,
+            payload: serde_json::to_value(payload)?,
+        };
+        let mut line = serde_json::to_string(&request)?;
+        line.push('\n');
+
+        if let Err(e) = worker.stdin.write_all(line.as_bytes()).await {
+            worker.pending.lock().await.remove(&id);
+            worker.alive.store(false, Ordering::Relaxed);
+            return Err(BridgeError::Io(e));
+        }
+        worker.stdin.flush().await?;
+
+        match rx.await {
+            Ok(Ok(value)) => Ok(serde_json::from_value(value)?),
+            Ok(Err(message)) => Err(BridgeError::InvalidOutput(message)),
+            Err(_) => {
+                worker.alive.store(false, Ordering::Relaxed);
+                Err(BridgeError::InvalidOutput(
+                    "worker closed before responding".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Whether the underlying worker is still considered usable.
+    pub fn is_healthy(&self) -> bool {
+        self.worker
+            .as_ref()
+            .map(|w| w.alive.load(Ordering::Relaxed))
+            .unwrap_or(false)
+    }
+
+    /// Mark the underlying worker dead so [`WorkerPool::release`] discards it
+    /// instead of returning it to the idle queue. Use this when a caller
+    /// gives up on a pending `call` (e.g. its own timeout fires) and the
+    /// worker can no longer be trusted to be in sync with the protocol.
+    pub fn retire(&self) {
+        if let Some(worker) = &self.worker {
+            worker.alive.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for PooledWorker {
+    fn drop(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            let pool = Arc::clone(&self.pool);
+            let permit = self.permit.take();
+            tokio::spawn(async move {
+                pool.release(worker).await;
+                // Hold the permit until the worker is actually idle again,
+                // so the pool's live worker count never exceeds max_size.
+                drop(permit);
+            });
+        }
+    }
+}
+
+/// A pool of persistent Python worker processes, checked out like a
+/// connection pool via [`WorkerPool::acquire`].
+pub struct WorkerPool {
+    config: WorkerPoolConfig,
+    /// Idle workers paired with the instant they became idle, so the
+    /// background reaper spawned in [`WorkerPool::new`] knows which ones
+    /// have sat unused past `config.idle_timeout`.
+    idle: Mutex<VecDeque<(Worker, Instant)>>,
+    next_id: AtomicU64,
+    /// One permit per `max_size` worker slot. `acquire()` must hold a
+    /// permit before it's allowed to spawn a worker, which is what caps
+    /// the total (idle + outstanding) worker count at `max_size`.
+    semaphore: Arc<Semaphore>,
+}
+
+impl std::fmt::Debug for WorkerPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerPool")
+            .field("command", &self.config.command)
+            .field("max_size", &self.config.max_size)
+            .finish()
+    }
+}
+
+impl WorkerPool {
+    /// Launch `config.max_size` workers up front and return the pool, with a
+    /// background task that reaps workers idle past `config.idle_timeout`.
+    pub async fn new(config: WorkerPoolConfig) -> BridgeResult<Arc<Self>> {
+        let mut idle = VecDeque::with_capacity(config.max_size);
+        for _ in 0..config.max_size {
+            idle.push_back((Self::spawn_worker(&config).await?, Instant::now()));
+        }
+
+        let semaphore = Arc::new(Semaphore::new(config.max_size));
+        let idle_timeout = config.idle_timeout;
+
+        let pool = Arc::new(Self {
+            config,
+            idle: Mutex::new(idle),
+            next_id: AtomicU64::new(1),
+            semaphore,
+        });
+
+        // Weak so a forgotten pool's reaper task doesn't keep it alive forever.
+        // `tokio::time::interval` panics on a zero duration, so a config that
+        // sets idle_timeout to zero just disables reaping rather than
+        // crashing the background task.
+        if !idle_timeout.is_zero() {
+            let weak_pool = Arc::downgrade(&pool);
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(idle_timeout);
+                ticker.tick().await; // first tick fires immediately; skip it
+                loop {
+                    ticker.tick().await;
+                    match weak_pool.upgrade() {
+                        Some(pool) => pool.reap_idle().await,
+                        None => break,
+                    }
+                }
+            });
+        }
+
+        Ok(pool)
+    }
+
+    /// Drop idle workers that have sat unused past `config.idle_timeout`.
+    /// Reaped workers don't hold a semaphore permit, so this only shrinks
+    /// the idle queue; `acquire` will spawn a replacement on demand.
+    async fn reap_idle(&self) {
+        let mut idle = self.idle.lock().await;
+        let before = idle.len();
+        idle.retain(|(_, idle_since)| idle_since.elapsed() < self.config.idle_timeout);
+        let reaped = before - idle.len();
+        if reaped > 0 {
+            debug!("Reaped {} worker(s) idle past {:?}", reaped, self.config.idle_timeout);
+        }
+    }
+
+    async fn spawn_worker(config: &WorkerPoolConfig) -> BridgeResult<Worker> {
+        let mut cmd = TokioCommand::new(&config.command);
+        cmd.args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        if let Some(ref dir) = config.working_dir {
+            cmd.current_dir(dir);
+        }
+        for (key, value) in &config.env_vars {
+            cmd.env(key, value);
+        }
+
+        let mut child = cmd.spawn().map_err(BridgeError::Io)?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| BridgeError::InvalidOutput("worker has no stdin".to_string()))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| BridgeError::InvalidOutput("worker has no stdout".to_string()))?;
+
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+        let alive = Arc::new(AtomicBool::new(true));
+
+        let reader_pending = Arc::clone(&pending);
+        let reader_alive = Arc::clone(&alive);
+        let reader_task = tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            loop {
+                match lines.next_line().await {
+                    Ok(Some(line)) => {
+                        if line.trim().is_empty() {
+                            continue;
+                        }
+                        match serde_json::from_str::<WorkerResponse>(&line) {
+                            Ok(response) => {
+                                if let Some(tx) = reader_pending.lock().await.remove(&response.id) {
+                                    let outcome = match response.error {
+                                        Some(message) => Err(message),
+                                        None => Ok(response.result.unwrap_or(Value::Null)),
+                                    };
+                                    let _ = tx.send(outcome);
+                                }
+                            }
+                            Err(e) => {
+                                warn!("Worker emitted unparseable response: {} ({})", line, e);
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        debug!("Worker stdout closed");
+                        break;
+                    }
+                    Err(e) => {
+                        error!("Error reading worker stdout: {}", e);
+                        break;
+                    }
+                }
+            }
+            reader_alive.store(false, Ordering::Relaxed);
+            // The worker is gone (stdout closed or errored) with calls still
+            // in flight: nothing will ever remove their entries or fire their
+            // oneshot, so `PooledWorker::call`'s `rx.await` would hang
+            // forever. Fail every pending call instead of leaving its sender
+            // dangling.
+            for (_, tx) in reader_pending.lock().await.drain() {
+                let _ = tx.send(Err("worker process exited before responding".to_string()));
+            }
+        });
+
+        Ok(Worker {
+            child,
+            stdin,
+            pending,
+            alive,
+            reader_task,
+        })
+    }
+
+    /// Hand out an idle worker, spawning a new one if none are idle, or
+    /// reaping and replacing one that failed its health check. Blocks once
+    /// `max_size` workers are already idle or checked out, instead of
+    /// growing the pool without bound.
+    pub async fn acquire(self: &Arc<Self>) -> BridgeResult<PooledWorker> {
+        let permit = Arc::clone(&self.semaphore)
+            .acquire_owned()
+            .await
+            .map_err(|_| BridgeError::InvalidOutput("worker pool has been shut down".to_string()))?;
+
+        loop {
+            let candidate = {
+                let mut idle = self.idle.lock().await;
+                idle.pop_front()
+            };
+
+            let mut worker = match candidate {
+                Some((worker, _idle_since)) => worker,
+                // Spawning here is bounded: we only reach it while holding
+                // a permit, and there are only `max_size` permits total.
+                None => Self::spawn_worker(&self.config).await?,
+            };
+
+            if !worker.alive.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            if !self.ping(&mut worker).await {
+                warn!("Worker failed health check, retiring it");
+                continue;
+            }
+
+            return Ok(PooledWorker {
+                pool: Arc::clone(self),
+                worker: Some(worker),
+                permit: Some(permit),
+            });
+        }
+    }
+
+    async fn ping(&self, worker: &mut Worker) -> bool {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        worker.pending.lock().await.insert(id, tx);
+
+        let request = WorkerRequest {
+            id,
+            payload: serde_json::json!({ "__ping__": true }),
+        };
+        let Ok(mut line) = serde_json::to_string(&request) else {
+            return false;
+        };
+        line.push('\n');
+
+        if worker.stdin.write_all(line.as_bytes()).await.is_err() {
+            return false;
+        }
+        if worker.stdin.flush().await.is_err() {
+            return false;
+        }
+
+        matches!(
+            tokio::time::timeout(self.config.ping_timeout, rx).await,
+            Ok(Ok(_))
+        )
+    }
+
+    async fn release(&self, worker: Worker) {
+        if worker.alive.load(Ordering::Relaxed) {
+            self.idle.lock().await.push_back((worker, Instant::now()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Serialize)]
+    struct EchoRequest {
+        message: String,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct EchoResponse {
+        echo: String,
+    }
+
+    // Minimal worker implementing the pool's framed protocol: echoes
+    // `payload.message` back, and answers the reserved ping frame.
+    const ECHO_WORKER_SCRIPT: &str = r#"
+import json, sys
+for line in sys.stdin:
+    line = line.strip()
+    if not line:
+        continue
+    req = json.loads(line)
+    payload = req.get("payload", {})
+    if isinstance(payload, dict) and payload.get("__ping__"):
+        print(json.dumps({"id": req["id"], "result": {"pong": True}}))
+    else:
+        print(json.dumps({"id": req["id"], "result": {"echo": payload.get("message")}}))
+    sys.stdout.flush()
+"#;
+
+    #[tokio::test]
+    async fn test_worker_pool_roundtrip() {
+        let config = WorkerPoolConfig {
+            args: vec!["-c".to_string(), ECHO_WORKER_SCRIPT.to_string()],
+            max_size: 2,
+            ..Default::default()
+        };
+
+        let pool = WorkerPool::new(config).await.unwrap();
+        let mut worker = pool.acquire().await.unwrap();
+
+        let response: EchoResponse = worker
+            .call(&EchoRequest {
+                message: "hi".to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.echo, "hi");
+    }
+
+    #[tokio::test]
+    async fn test_call_fails_instead_of_hanging_when_worker_exits_without_responding() {
+        // Answers the ping (so acquire()'s health check passes) but exits
+        // without ever responding to a real payload.
+        let script = r#"
+import json, sys
+line = sys.stdin.readline()
+req = json.loads(line)
+print(json.dumps({"id": req["id"], "result": {"pong": True}}))
+sys.stdout.flush()
+sys.exit(0)
+"#;
+        let config = WorkerPoolConfig {
+            args: vec!["-c".to_string(), script.to_string()],
+            max_size: 1,
+            ..Default::default()
+        };
+
+        let pool = WorkerPool::new(config).await.unwrap();
+        let mut worker = pool.acquire().await.unwrap();
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            worker.call::<EchoRequest, EchoResponse>(&EchoRequest {
+                message: "hi".to_string(),
+            }),
+        )
+        .await
+        .expect("call() should resolve once the worker's stdout closes, not hang");
+
+        assert!(matches!(result, Err(BridgeError::InvalidOutput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_blocks_once_max_size_checked_out() {
+        let config = WorkerPoolConfig {
+            args: vec!["-c".to_string(), ECHO_WORKER_SCRIPT.to_string()],
+            max_size: 2,
+            ..Default::default()
+        };
+
+        let pool = WorkerPool::new(config).await.unwrap();
+        let _first = pool.acquire().await.unwrap();
+        let _second = pool.acquire().await.unwrap();
+
+        // Both permits are checked out, so a third acquire must block
+        // rather than spawning a worker past max_size.
+        let blocked = tokio::time::timeout(Duration::from_millis(200), pool.acquire()).await;
+        assert!(blocked.is_err(), "acquire() should block at max_size");
+
+        drop(_second);
+
+        // Releasing a checked-out worker frees its permit, so the next
+        // acquire should now succeed.
+        let third = tokio::time::timeout(Duration::from_secs(5), pool.acquire()).await;
+        assert!(third.is_ok(), "acquire() should unblock once a permit frees up");
+    }
+}